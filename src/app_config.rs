@@ -1,4 +1,5 @@
 use config::{Config, ConfigError, File};
+use secrecy::SecretString;
 use serde::Deserialize;
 
 #[derive(Debug, Deserialize)]
@@ -11,7 +12,10 @@ pub struct AmberConfig {
 #[allow(unused)]
 pub struct ApiToken {
     pub name: String,
-    pub psk: String,
+    // Optional here because `AMBER_PSK` is allowed to be the only place the
+    // token lives; `AppConfig::get` fills this in from the env var and
+    // errors if neither source provides it.
+    pub psk: Option<SecretString>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -22,11 +26,28 @@ pub struct AppConfig {
 }
 
 impl AppConfig {
+    // `AMBER_PSK`, when set, overrides whatever `psk` is in config.toml so the
+    // token never has to live on disk.
+    const PSK_ENV_VAR: &'static str = "AMBER_PSK";
+
     pub async fn get() -> Result<Self, ConfigError> {
         let config = Config::builder()
             .add_source(File::with_name("config.toml"))
             .build()?;
 
-        config.try_deserialize()
+        let mut config: Self = config.try_deserialize()?;
+
+        if let Ok(psk) = std::env::var(Self::PSK_ENV_VAR) {
+            config.apitoken.psk = Some(SecretString::new(psk));
+        }
+
+        if config.apitoken.psk.is_none() {
+            return Err(ConfigError::Message(format!(
+                "no API token configured: set `apitoken.psk` in config.toml or the {} environment variable",
+                Self::PSK_ENV_VAR
+            )));
+        }
+
+        Ok(config)
     }
 }
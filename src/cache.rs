@@ -0,0 +1,225 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+
+/// A TTL-keyed byte-blob cache, keyed on URL+query. Implementations only see
+/// opaque bytes so `RestClient` can cache the raw response body and keep
+/// deserialization unchanged.
+#[async_trait]
+pub trait Cache: Send + Sync {
+    async fn get(&self, key: &str) -> Option<Vec<u8>>;
+    async fn set(&self, key: &str, value: Vec<u8>, ttl: Duration);
+}
+
+struct Entry {
+    value: Vec<u8>,
+    expires_at: Instant,
+}
+
+/// Simple process-local cache. Good enough for a one-shot CLI invocation;
+/// entries don't survive the process exiting.
+#[derive(Default)]
+pub struct InMemoryCache {
+    entries: Mutex<HashMap<String, Entry>>,
+}
+
+impl InMemoryCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl Cache for InMemoryCache {
+    async fn get(&self, key: &str) -> Option<Vec<u8>> {
+        let entries = self.entries.lock().expect("cache mutex poisoned");
+        let entry = entries.get(key)?;
+        if entry.expires_at <= Instant::now() {
+            return None;
+        }
+        Some(entry.value.clone())
+    }
+
+    async fn set(&self, key: &str, value: Vec<u8>, ttl: Duration) {
+        let mut entries = self.entries.lock().expect("cache mutex poisoned");
+        entries.insert(
+            key.to_string(),
+            Entry {
+                value,
+                expires_at: Instant::now() + ttl,
+            },
+        );
+    }
+}
+
+/// On-disk cache keyed by a hash of the cache key, with the expiry stamped
+/// into the file itself. Unlike `InMemoryCache`, entries survive the process
+/// exiting, so back-to-back CLI invocations can actually share a response.
+pub struct FileCache {
+    dir: PathBuf,
+}
+
+impl FileCache {
+    pub fn new(dir: impl Into<PathBuf>) -> std::io::Result<Self> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)?;
+        // Cached bytes are raw API responses (NMI, usage, price data), so
+        // keep the directory from being world/group-readable.
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o700))?;
+        }
+        Ok(Self { dir })
+    }
+
+    fn path_for(&self, key: &str) -> PathBuf {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        self.dir.join(format!("{:016x}.cache", hasher.finish()))
+    }
+}
+
+#[cfg(unix)]
+async fn restrict_to_owner(path: &std::path::Path) {
+    use std::os::unix::fs::PermissionsExt;
+    let _ = tokio::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600)).await;
+}
+
+#[cfg(not(unix))]
+async fn restrict_to_owner(_path: &std::path::Path) {}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+#[async_trait]
+impl Cache for FileCache {
+    async fn get(&self, key: &str) -> Option<Vec<u8>> {
+        let path = self.path_for(key);
+        let bytes = tokio::fs::read(&path).await.ok()?;
+        if bytes.len() < 8 {
+            return None;
+        }
+        let (expiry, value) = bytes.split_at(8);
+        let expires_at = u64::from_le_bytes(expiry.try_into().ok()?);
+
+        if unix_now() >= expires_at {
+            let _ = tokio::fs::remove_file(&path).await;
+            return None;
+        }
+
+        Some(value.to_vec())
+    }
+
+    async fn set(&self, key: &str, value: Vec<u8>, ttl: Duration) {
+        let path = self.path_for(key);
+        let expires_at = unix_now() + ttl.as_secs();
+
+        let mut bytes = Vec::with_capacity(8 + value.len());
+        bytes.extend_from_slice(&expires_at.to_le_bytes());
+        bytes.extend_from_slice(&value);
+
+        if tokio::fs::write(&path, bytes).await.is_ok() {
+            restrict_to_owner(&path).await;
+        }
+    }
+}
+
+/// Redis-backed cache so repeated runs across processes (e.g. a cron job)
+/// can still share cached responses, as in dls_rs.
+#[cfg(feature = "redis-cache")]
+pub struct RedisCache {
+    client: redis::Client,
+}
+
+#[cfg(feature = "redis-cache")]
+impl RedisCache {
+    pub fn new(redis_url: &str) -> redis::RedisResult<Self> {
+        Ok(Self {
+            client: redis::Client::open(redis_url)?,
+        })
+    }
+}
+
+#[cfg(feature = "redis-cache")]
+#[async_trait]
+impl Cache for RedisCache {
+    async fn get(&self, key: &str) -> Option<Vec<u8>> {
+        let mut conn = self.client.get_async_connection().await.ok()?;
+        redis::AsyncCommands::get(&mut conn, key).await.ok()
+    }
+
+    async fn set(&self, key: &str, value: Vec<u8>, ttl: Duration) {
+        let Ok(mut conn) = self.client.get_async_connection().await else {
+            return;
+        };
+        let _: redis::RedisResult<()> =
+            redis::AsyncCommands::set_ex(&mut conn, key, value, ttl.as_secs().max(1) as usize)
+                .await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_cache(name: &str) -> FileCache {
+        let dir = std::env::temp_dir().join(format!(
+            "amber-cli-cache-test-{}-{}-{:?}",
+            name,
+            std::process::id(),
+            std::thread::current().id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        FileCache::new(dir).expect("failed to create test cache dir")
+    }
+
+    #[tokio::test]
+    async fn file_cache_round_trips_a_value_before_it_expires() {
+        let cache = test_cache("round-trip");
+        cache.set("k", b"hello".to_vec(), Duration::from_secs(60)).await;
+        assert_eq!(cache.get("k").await, Some(b"hello".to_vec()));
+    }
+
+    #[tokio::test]
+    async fn file_cache_expires_entries_once_their_ttl_has_passed() {
+        let cache = test_cache("expiry");
+        cache.set("k", b"hello".to_vec(), Duration::from_secs(0)).await;
+        tokio::time::sleep(Duration::from_millis(1100)).await;
+        assert_eq!(cache.get("k").await, None);
+    }
+
+    #[tokio::test]
+    async fn file_cache_returns_none_for_a_missing_key() {
+        let cache = test_cache("missing");
+        assert_eq!(cache.get("nope").await, None);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn file_cache_restricts_directory_and_file_permissions_to_the_owner() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let cache = test_cache("permissions");
+        cache.set("k", b"hello".to_vec(), Duration::from_secs(60)).await;
+
+        let dir_mode = std::fs::metadata(&cache.dir).unwrap().permissions().mode() & 0o777;
+        assert_eq!(dir_mode, 0o700);
+
+        let file_mode = std::fs::metadata(cache.path_for("k"))
+            .unwrap()
+            .permissions()
+            .mode()
+            & 0o777;
+        assert_eq!(file_mode, 0o600);
+    }
+}
@@ -0,0 +1,3 @@
+pub mod app_config;
+pub mod cache;
+pub mod rest_client;
@@ -1,210 +1,509 @@
-use anyhow::Result;
-use iso8601_timestamp::Timestamp;
-use reqwest::Client;
-use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use chrono::NaiveDate;
+use clap::{Parser, Subcommand, ValueEnum};
+use serde::Serialize;
 
 use amber_client::app_config::AppConfig;
+use amber_client::cache::{FileCache, InMemoryCache};
+#[cfg(feature = "redis-cache")]
+use amber_client::cache::RedisCache;
+use amber_client::rest_client::{CurrentUsage, PriceInterval, RestClient, SiteDetails};
+
+#[derive(Parser, Debug)]
+#[command(name = "amber-cli", about = "Query the Amber Electric API")]
+struct Cli {
+    /// Disable the response cache and always hit the Amber API (shorthand
+    /// for --cache-backend none)
+    #[arg(long, global = true)]
+    no_cache: bool,
+    /// Which cache backend to use. `memory` (the default) only helps
+    /// within this process and leaves no disk footprint; `file`/`redis`
+    /// persist across invocations but must be opted into explicitly.
+    #[arg(long, value_enum, default_value_t = CacheBackend::Memory, global = true)]
+    cache_backend: CacheBackend,
+    /// Directory for the `file` cache backend (default: a per-user cache
+    /// dir, e.g. $XDG_CACHE_HOME/amber-cli or ~/.cache/amber-cli)
+    #[arg(long, global = true)]
+    cache_dir: Option<PathBuf>,
+    /// Redis URL for the `redis` cache backend, e.g. redis://127.0.0.1/
+    #[cfg(feature = "redis-cache")]
+    #[arg(long, global = true)]
+    redis_url: Option<String>,
+    /// Override the cache TTL (in seconds) for every cached response
+    #[arg(long, global = true)]
+    cache_ttl: Option<u64>,
+    /// Output format
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text, global = true)]
+    format: OutputFormat,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum CacheBackend {
+    /// Always hit the Amber API.
+    None,
+    /// Cache within this process only; lost as soon as it exits.
+    Memory,
+    /// Cache on disk under --cache-dir, shared across invocations.
+    File,
+    /// Cache in Redis, shared across invocations and machines.
+    #[cfg(feature = "redis-cache")]
+    Redis,
+}
 
-#[derive(Serialize, Deserialize, Debug)]
-#[serde(rename_all = "camelCase")]
-struct SiteDetails {
-    active_from: Timestamp,
-    channels: Vec<SiteChannels>,
-    id: String,
-    network: String,
-    nmi: String,
-    status: String,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-struct SiteChannels {
-    identifier: String,
-    tariff: String,
-    // type is a reserved word, so rename it.
-    #[serde(rename = "type")]
-    tariff_type: String,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-#[serde(rename_all = "camelCase")]
-struct CurrentPrices {
-    // type is a reserved word, so rename it.
-    #[serde(rename = "type")]
+// Each user gets their own cache dir so cached NMI/usage/price data for one
+// account on a shared machine is never readable (or collidable) by another.
+fn default_cache_dir() -> PathBuf {
+    if let Ok(xdg_cache) = std::env::var("XDG_CACHE_HOME") {
+        return PathBuf::from(xdg_cache).join("amber-cli");
+    }
+    if let Ok(home) = std::env::var("HOME") {
+        return PathBuf::from(home).join(".cache").join("amber-cli");
+    }
+    std::env::temp_dir().join(format!("amber-cli-cache-{}", whoami_fallback()))
+}
+
+// Last-resort disambiguator when neither XDG_CACHE_HOME nor HOME is set, so
+// two users on the same box don't fall back to the exact same temp dir.
+fn whoami_fallback() -> String {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("LOGNAME"))
+        .unwrap_or_else(|_| "unknown".to_string())
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum OutputFormat {
+    Text,
+    Json,
+    Csv,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// List the sites on the configured account
+    Sites,
+    /// Show current electricity prices for the account's site
+    Prices {
+        /// Interval resolution in minutes
+        #[arg(long, default_value_t = 30)]
+        resolution: u16,
+        /// Number of intervals to look ahead into the forecast
+        #[arg(long)]
+        next: Option<u32>,
+        /// Number of intervals to look back into history
+        #[arg(long)]
+        previous: Option<u32>,
+    },
+    /// Fetch usage data for a date range
+    Usage {
+        /// Start of the date range, e.g. 2023-09-12
+        #[arg(long)]
+        start: NaiveDate,
+        /// End of the date range, e.g. 2023-09-13
+        #[arg(long)]
+        end: NaiveDate,
+        /// Interval resolution in minutes
+        #[arg(long, default_value_t = 30)]
+        resolution: u16,
+    },
+}
+
+// CSV rows for the two endpoints that return nested timestamps/structs;
+// flatten `tariff_information.period` and ISO8601 fields into plain columns.
+#[derive(Serialize)]
+struct PriceIntervalRow {
     interval_type: String,
-    date: Timestamp,
+    date: String,
     duration: u8,
-    start_time: Timestamp,
-    end_time: Timestamp,
-    nem_time: Timestamp,
+    start_time: String,
+    end_time: String,
+    nem_time: Option<String>,
     per_kwh: f32,
     renewables: f32,
     spot_per_kwh: f32,
     channel_type: String,
     spike_status: String,
-    tariff_information: TariffInformation,
+    tariff_period: String,
     descriptor: String,
-    estimate: bool,
+    estimate: Option<bool>,
+    range_min: Option<f32>,
+    range_max: Option<f32>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-struct TariffInformation {
-    period: String,
+impl From<&PriceInterval> for PriceIntervalRow {
+    fn from(interval: &PriceInterval) -> Self {
+        match interval {
+            PriceInterval::ActualInterval {
+                date,
+                duration,
+                start_time,
+                end_time,
+                per_kwh,
+                renewables,
+                spot_per_kwh,
+                channel_type,
+                spike_status,
+                tariff_information,
+                descriptor,
+            } => PriceIntervalRow {
+                interval_type: "ActualInterval".to_string(),
+                date: date.to_string(),
+                duration: *duration,
+                start_time: start_time.to_string(),
+                end_time: end_time.to_string(),
+                nem_time: None,
+                per_kwh: *per_kwh,
+                renewables: *renewables,
+                spot_per_kwh: *spot_per_kwh,
+                channel_type: channel_type.clone(),
+                spike_status: spike_status.to_string(),
+                tariff_period: tariff_information.period.clone(),
+                descriptor: descriptor.to_string(),
+                estimate: None,
+                range_min: None,
+                range_max: None,
+            },
+            PriceInterval::CurrentInterval {
+                date,
+                duration,
+                start_time,
+                end_time,
+                nem_time,
+                per_kwh,
+                renewables,
+                spot_per_kwh,
+                channel_type,
+                spike_status,
+                tariff_information,
+                descriptor,
+                estimate,
+            } => PriceIntervalRow {
+                interval_type: "CurrentInterval".to_string(),
+                date: date.to_string(),
+                duration: *duration,
+                start_time: start_time.to_string(),
+                end_time: end_time.to_string(),
+                nem_time: Some(nem_time.to_string()),
+                per_kwh: *per_kwh,
+                renewables: *renewables,
+                spot_per_kwh: *spot_per_kwh,
+                channel_type: channel_type.clone(),
+                spike_status: spike_status.to_string(),
+                tariff_period: tariff_information.period.clone(),
+                descriptor: descriptor.to_string(),
+                estimate: Some(*estimate),
+                range_min: None,
+                range_max: None,
+            },
+            PriceInterval::ForecastInterval {
+                date,
+                duration,
+                start_time,
+                end_time,
+                per_kwh,
+                renewables,
+                spot_per_kwh,
+                channel_type,
+                spike_status,
+                tariff_information,
+                descriptor,
+                estimate,
+                range,
+            } => PriceIntervalRow {
+                interval_type: "ForecastInterval".to_string(),
+                date: date.to_string(),
+                duration: *duration,
+                start_time: start_time.to_string(),
+                end_time: end_time.to_string(),
+                nem_time: None,
+                per_kwh: *per_kwh,
+                renewables: *renewables,
+                spot_per_kwh: *spot_per_kwh,
+                channel_type: channel_type.clone(),
+                spike_status: spike_status.to_string(),
+                tariff_period: tariff_information.period.clone(),
+                descriptor: descriptor.to_string(),
+                estimate: Some(*estimate),
+                range_min: range.as_ref().map(|r| r.min),
+                range_max: range.as_ref().map(|r| r.max),
+            },
+        }
+    }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-#[serde(rename_all = "camelCase")]
-struct CurrentUsage {
-    #[serde(rename = "type")]
+#[derive(Serialize)]
+struct UsageRow {
     price_type: String,
+    date: String,
     duration: u8,
-    date: Timestamp,
-    end_time: Timestamp,
+    start_time: String,
+    end_time: String,
+    nem_time: String,
     quality: String,
     kwh: f32,
-    nem_time: Timestamp,
     per_kwh: f32,
-    channel_type: String,
-    channel_identifier: String,
     cost: f32,
     renewables: f32,
     spot_per_kwh: f32,
-    start_time: Timestamp,
+    channel_type: String,
+    channel_identifier: String,
     spike_status: String,
-    tariff_information: TariffInformation,
+    tariff_period: String,
     descriptor: String,
 }
 
-#[derive(Clone)]
-struct RestClient {
-    url: String,
-    auth_token: String,
-    client: reqwest::Client,
+impl From<&CurrentUsage> for UsageRow {
+    fn from(usage: &CurrentUsage) -> Self {
+        UsageRow {
+            price_type: usage.price_type.clone(),
+            date: usage.date.to_string(),
+            duration: usage.duration,
+            start_time: usage.start_time.to_string(),
+            end_time: usage.end_time.to_string(),
+            nem_time: usage.nem_time.to_string(),
+            quality: usage.quality.clone(),
+            kwh: usage.kwh,
+            per_kwh: usage.per_kwh,
+            cost: usage.cost,
+            renewables: usage.renewables,
+            spot_per_kwh: usage.spot_per_kwh,
+            channel_type: usage.channel_type.clone(),
+            channel_identifier: usage.channel_identifier.clone(),
+            spike_status: usage.spike_status.to_string(),
+            tariff_period: usage.tariff_information.period.clone(),
+            descriptor: usage.descriptor.to_string(),
+        }
+    }
 }
 
-#[tokio::main]
-async fn main() -> Result<()> {
-    impl RestClient {
-        pub fn new_client(url: String, auth_token: String) -> Self {
-            Self {
-                url,
-                auth_token,
-                client: Client::new(),
-            }
+fn print_price_interval(interval: &PriceInterval) {
+    match interval {
+        PriceInterval::ActualInterval {
+            start_time,
+            end_time,
+            per_kwh,
+            spike_status,
+            descriptor,
+            ..
+        }
+        | PriceInterval::CurrentInterval {
+            start_time,
+            end_time,
+            per_kwh,
+            spike_status,
+            descriptor,
+            ..
+        }
+        | PriceInterval::ForecastInterval {
+            start_time,
+            end_time,
+            per_kwh,
+            spike_status,
+            descriptor,
+            ..
+        } => {
+            println!("Window starts at: {}", start_time);
+            println!("Window ends at: {}", end_time);
+            println!("Per KWH price(c/kWh): {}", per_kwh);
+            println!("Is this window in a spike?: {}", spike_status);
+            println!("Overall rate status: {}", descriptor);
+            println!("-------------------------------------------------------------------");
         }
+    }
+}
 
-        pub async fn get_site_data(&mut self) -> Result<Vec<SiteDetails>> {
-            let auth_token_header = format!("Bearer {}", &self.auth_token);
+fn emit_price_intervals(intervals: &[PriceInterval], format: OutputFormat) -> Result<()> {
+    match format {
+        OutputFormat::Text => {
+            for interval in intervals {
+                print_price_interval(interval);
+            }
+        }
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(intervals)?);
+        }
+        OutputFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(std::io::stdout());
+            for interval in intervals {
+                writer.serialize(PriceIntervalRow::from(interval))?;
+            }
+            writer.flush()?;
+        }
+    }
 
-            let response = self
-                .client
-                .get(&self.url)
-                .header("AUTHORIZATION", auth_token_header)
-                .header("CONTENT_TYPE", "application/json")
-                .header("ACCEPT", "application/json")
-                .send()
-                .await?
-                .json::<Vec<SiteDetails>>()
-                .await?;
+    Ok(())
+}
 
-            Ok(response)
+fn emit_usage(usage: &[CurrentUsage], format: OutputFormat) -> Result<()> {
+    match format {
+        OutputFormat::Text => {
+            for entry in usage {
+                println!(
+                    "{}: {} kWh, cost {}c, per kWh {}c",
+                    &entry.start_time, &entry.kwh, &entry.cost, &entry.per_kwh
+                );
+            }
+        }
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(usage)?);
         }
+        OutputFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(std::io::stdout());
+            for entry in usage {
+                writer.serialize(UsageRow::from(entry))?;
+            }
+            writer.flush()?;
+        }
+    }
 
-        pub async fn get_current_price_data(&mut self) -> Result<Vec<CurrentPrices>> {
-            let auth_token_header = format!("Bearer {}", &self.auth_token);
+    Ok(())
+}
 
-            let response = self
-                .client
-                .get(&self.url)
-                .header("AUTHORIZATION", auth_token_header)
-                .header("CONTENT_TYPE", "application/json")
-                .header("ACCEPT", "application/json")
-                .send()
-                .await?
-                .json::<Vec<CurrentPrices>>()
-                .await?;
+// One account can only have one site, so every call site extracts the
+// first one; a zero-site account (new sign-up, or a removed site) is a
+// valid API response, not a malformed one, so this errors rather than
+// panics.
+fn first_site(sites: &[SiteDetails]) -> Result<&SiteDetails> {
+    sites.first().context("account has no sites")
+}
 
-            Ok(response)
-        }
+async fn get_site_id(client: &RestClient) -> Result<String> {
+    let sites = client.sites().await?;
+
+    Ok(first_site(&sites)?.id.clone())
+}
 
-        pub async fn get_usage_data(&mut self) -> Result<Vec<CurrentUsage>> {
-            let auth_token_header = format!("Bearer {}", &self.auth_token);
+async fn run_sites(client: &RestClient, format: OutputFormat) -> Result<()> {
+    let sites = client.sites().await?;
 
-            let response = self
-                .client
-                .get(&self.url)
-                .header("AUTHORIZATION", auth_token_header)
-                .header("CONTENT_TYPE", "application/json")
-                .header("ACCEPT", "application/json")
-                .send()
-                .await?
-                .json::<Vec<CurrentUsage>>()
-                .await?;
+    match format {
+        OutputFormat::Text => {
+            let site = first_site(&sites)?;
 
-            Ok(response)
+            println!("-------------------------------------------------------------------");
+            println!("My site details");
+            println!("Grid network: {}", &site.network);
+            println!("My house meter NMI number: {}", &site.nmi);
+            println!("Status: {}", &site.status);
+            println!("-------------------------------------------------------------------");
+        }
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&sites)?);
+        }
+        OutputFormat::Csv => {
+            bail!("csv output is not supported for sites (nested channel list)")
         }
     }
 
-    // get config
-    let config = AppConfig::get().await?;
-    let auth_token = config.apitoken.psk;
-    let base_url = config.amberconfig.base_url;
-
-    // get site details
-    let sites_url = format!("{}/sites", base_url);
-    let mut user_site_details = RestClient::new_client(sites_url, auth_token.clone());
-    let user_site_data = user_site_details.get_site_data().await?;
-
-    // one account can only have one site, so extract from array
-    let user_site_data = user_site_data
-        .get(0)
-        .expect("Malformed array/invalid index[0]");
-
-    let site_id = &user_site_data.id;
-
-    // end site details
-
-    // get current price details
-    let current_price_url = format!(
-        "{}/sites/{}/prices/current?&resolution=30",
-        base_url, site_id
-    );
-    let mut current_price_details = RestClient::new_client(current_price_url, auth_token.clone());
-    let current_price_data = current_price_details.get_current_price_data().await?;
-
-    // One site can only have one set of current prices so extract from array
-    let current_price_data = current_price_data
-        .get(0)
-        .expect("Malformed array/invalid index[0]");
-
-    // end current price details
-
-    // get usage dat
-    let usage_data_url = format!(
-        "{}/sites/{}/usage?startDate=2023-09-12&endDate=2023-09-13&resolution=30'",
-        base_url, site_id
-    );
-    let mut usage_details = RestClient::new_client(usage_data_url, auth_token.clone());
-    let usage_data = usage_details.get_usage_data().await?;
-
-    // end usage data
-
-    println!("-------------------------------------------------------------------");
-    println!("My site details");
-    println!("Grid network: {}", &user_site_data.network);
-    println!("My house meter NMI number: {}", &user_site_data.nmi);
-    println!("Status: {}", &user_site_data.status);
-    println!("-------------------------------------------------------------------");
-    println!("Current 30min price window rate");
-    println!("Window stats at: {}", &current_price_data.start_time);
-    println!("Window ends at: {}", &current_price_data.end_time);
-    println!("Per KWH price(c/kWh): {}", &current_price_data.per_kwh);
-    println!(
-        "Is this window in a spike?: {}",
-        &current_price_data.spike_status
-    );
-    println!("Overall rate status: {}", &current_price_data.descriptor);
-    println!("-------------------------------------------------------------------");
-    //println!("{:#?}", usage_data);
+    Ok(())
+}
+
+async fn run_prices(
+    client: &RestClient,
+    resolution: u16,
+    next: Option<u32>,
+    previous: Option<u32>,
+    format: OutputFormat,
+) -> Result<()> {
+    let site_id = get_site_id(client).await?;
+
+    if next.is_some() || previous.is_some() {
+        let intervals = client
+            .price_forecast(&site_id, resolution, next, previous)
+            .await?;
+
+        emit_price_intervals(&intervals, format)?;
+    } else {
+        let current_price_data = client.prices_current(&site_id, resolution).await?;
+
+        emit_price_intervals(&current_price_data, format)?;
+    }
 
     Ok(())
 }
+
+async fn run_usage(
+    client: &RestClient,
+    start: NaiveDate,
+    end: NaiveDate,
+    resolution: u16,
+    format: OutputFormat,
+) -> Result<()> {
+    if start > end {
+        bail!("--start ({}) must not be after --end ({})", start, end);
+    }
+
+    let site_id = get_site_id(client).await?;
+    let usage_data = client.usage(&site_id, start, end, resolution).await?;
+
+    emit_usage(&usage_data, format)?;
+
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let cli = Cli::parse();
+
+    let config = AppConfig::get().await?;
+    // `AppConfig::get` already errors out if neither `config.toml` nor
+    // `AMBER_PSK` provided a token, so this is always populated here.
+    let psk = config
+        .apitoken
+        .psk
+        .expect("AppConfig::get guarantees a psk is present");
+    let mut client = RestClient::new_client(config.amberconfig.base_url, psk);
+
+    let cache_backend = if cli.no_cache {
+        CacheBackend::None
+    } else {
+        cli.cache_backend
+    };
+
+    match cache_backend {
+        CacheBackend::None => {}
+        CacheBackend::Memory => {
+            client = client.with_cache(Arc::new(InMemoryCache::new()));
+        }
+        CacheBackend::File => {
+            let cache_dir = cli.cache_dir.clone().unwrap_or_else(default_cache_dir);
+            let cache = FileCache::new(&cache_dir).with_context(|| {
+                format!("failed to create cache directory {}", cache_dir.display())
+            })?;
+            client = client.with_cache(Arc::new(cache));
+        }
+        #[cfg(feature = "redis-cache")]
+        CacheBackend::Redis => {
+            let redis_url = cli
+                .redis_url
+                .as_deref()
+                .context("--cache-backend=redis requires --redis-url")?;
+            let cache =
+                RedisCache::new(redis_url).context("failed to create Redis cache client")?;
+            client = client.with_cache(Arc::new(cache));
+        }
+    }
+    if let Some(cache_ttl) = cli.cache_ttl {
+        client = client.with_cache_ttl_override(Duration::from_secs(cache_ttl));
+    }
+
+    match cli.command {
+        Command::Sites => run_sites(&client, cli.format).await,
+        Command::Prices {
+            resolution,
+            next,
+            previous,
+        } => run_prices(&client, resolution, next, previous, cli.format).await,
+        Command::Usage {
+            start,
+            end,
+            resolution,
+        } => run_usage(&client, start, end, resolution, cli.format).await,
+    }
+}
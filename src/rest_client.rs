@@ -1,8 +1,26 @@
-use anyhow::{bail, Result};
+use std::collections::hash_map::DefaultHasher;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, NaiveDate, Utc};
 use iso8601_timestamp::Timestamp;
-use reqwest::{Client, Response};
-use serde::{Deserialize, Serialize};
+use rand::Rng;
+use reqwest::{Client, Response, StatusCode};
+use secrecy::{ExposeSecret, SecretString};
+use serde::de::{self, DeserializeOwned, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use thiserror::Error;
+use tokio::time::sleep;
+
+use crate::cache::Cache;
+
+// Site details almost never change; cache them for a day by default.
+const SITE_CACHE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+// Fallback when a price/usage response is empty and no interval end_time
+// is available to derive a TTL from.
+const DEFAULT_INTERVAL_CACHE_TTL: Duration = Duration::from_secs(5 * 60);
 
 #[derive(Serialize, Deserialize, Debug, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -24,25 +42,178 @@ pub struct SiteChannels {
     pub tariff_type: String,
 }
 
+// Hand-written rather than derived so an API value we don't recognise
+// decodes to `Unknown(original)` instead of failing the whole response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpikeStatus {
+    None,
+    Potential,
+    Spike,
+    Unknown(String),
+}
+
+impl fmt::Display for SpikeStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SpikeStatus::None => "none",
+            SpikeStatus::Potential => "potential",
+            SpikeStatus::Spike => "spike",
+            SpikeStatus::Unknown(value) => value,
+        })
+    }
+}
+
+impl Serialize for SpikeStatus {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for SpikeStatus {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct SpikeStatusVisitor;
+
+        impl<'de> Visitor<'de> for SpikeStatusVisitor {
+            type Value = SpikeStatus;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a spike status string")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                Ok(match value {
+                    "none" => SpikeStatus::None,
+                    "potential" => SpikeStatus::Potential,
+                    "spike" => SpikeStatus::Spike,
+                    other => SpikeStatus::Unknown(other.to_owned()),
+                })
+            }
+        }
+
+        deserializer.deserialize_str(SpikeStatusVisitor)
+    }
+}
+
+// Same rationale as `SpikeStatus`: an `Unknown(original)` fallback so a new
+// descriptor value Amber adds never crashes decoding.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PriceDescriptor {
+    Negative,
+    VeryLow,
+    Low,
+    Neutral,
+    High,
+    Spike,
+    Unknown(String),
+}
+
+impl fmt::Display for PriceDescriptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            PriceDescriptor::Negative => "negative",
+            PriceDescriptor::VeryLow => "veryLow",
+            PriceDescriptor::Low => "low",
+            PriceDescriptor::Neutral => "neutral",
+            PriceDescriptor::High => "high",
+            PriceDescriptor::Spike => "spike",
+            PriceDescriptor::Unknown(value) => value,
+        })
+    }
+}
+
+impl Serialize for PriceDescriptor {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for PriceDescriptor {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct PriceDescriptorVisitor;
+
+        impl<'de> Visitor<'de> for PriceDescriptorVisitor {
+            type Value = PriceDescriptor;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a price descriptor string")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                Ok(match value {
+                    "negative" => PriceDescriptor::Negative,
+                    "veryLow" => PriceDescriptor::VeryLow,
+                    "low" => PriceDescriptor::Low,
+                    "neutral" => PriceDescriptor::Neutral,
+                    "high" => PriceDescriptor::High,
+                    "spike" => PriceDescriptor::Spike,
+                    other => PriceDescriptor::Unknown(other.to_owned()),
+                })
+            }
+        }
+
+        deserializer.deserialize_str(PriceDescriptorVisitor)
+    }
+}
+
+// The `/prices` endpoints return a mixed array of interval objects
+// distinguished by `type`. Forecast intervals carry an extra `range`, and
+// current intervals carry `estimate`/`nemTime` that actual intervals don't.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(tag = "type")]
+pub enum PriceInterval {
+    #[serde(rename_all = "camelCase")]
+    ActualInterval {
+        date: Timestamp,
+        duration: u8,
+        start_time: Timestamp,
+        end_time: Timestamp,
+        per_kwh: f32,
+        renewables: f32,
+        spot_per_kwh: f32,
+        channel_type: String,
+        spike_status: SpikeStatus,
+        tariff_information: TariffInformation,
+        descriptor: PriceDescriptor,
+    },
+    #[serde(rename_all = "camelCase")]
+    CurrentInterval {
+        date: Timestamp,
+        duration: u8,
+        start_time: Timestamp,
+        end_time: Timestamp,
+        nem_time: Timestamp,
+        per_kwh: f32,
+        renewables: f32,
+        spot_per_kwh: f32,
+        channel_type: String,
+        spike_status: SpikeStatus,
+        tariff_information: TariffInformation,
+        descriptor: PriceDescriptor,
+        estimate: bool,
+    },
+    #[serde(rename_all = "camelCase")]
+    ForecastInterval {
+        date: Timestamp,
+        duration: u8,
+        start_time: Timestamp,
+        end_time: Timestamp,
+        per_kwh: f32,
+        renewables: f32,
+        spot_per_kwh: f32,
+        channel_type: String,
+        spike_status: SpikeStatus,
+        tariff_information: TariffInformation,
+        descriptor: PriceDescriptor,
+        estimate: bool,
+        range: Option<ForecastRange>,
+    },
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
-pub struct CurrentPrices {
-    // type is a reserved word, so rename it.
-    #[serde(rename = "type")]
-    pub interval_type: String,
-    pub date: Timestamp,
-    pub duration: u8,
-    pub start_time: Timestamp,
-    pub end_time: Timestamp,
-    pub nem_time: Timestamp,
-    pub per_kwh: f32,
-    pub renewables: f32,
-    pub spot_per_kwh: f32,
-    pub channel_type: String,
-    pub spike_status: String,
-    pub tariff_information: TariffInformation,
-    pub descriptor: String,
-    pub estimate: bool,
+pub struct ForecastRange {
+    pub min: f32,
+    pub max: f32,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -68,16 +239,41 @@ pub struct CurrentUsage {
     pub renewables: f32,
     pub spot_per_kwh: f32,
     pub start_time: Timestamp,
-    pub spike_status: String,
+    pub spike_status: SpikeStatus,
     pub tariff_information: TariffInformation,
-    pub descriptor: String,
+    pub descriptor: PriceDescriptor,
+}
+
+#[derive(Clone, Debug)]
+pub struct RetryConfig {
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
 }
 
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+        }
+    }
+}
+
+// One client, one connection pool, shared across every endpoint. Methods
+// take a path and query pairs instead of each caller pre-formatting a full
+// URL, mirroring the Firefox Accounts HTTP client's shared-client pattern.
 #[derive(Clone)]
 pub struct RestClient {
-    pub url: String,
-    pub auth_token: String,
-    pub client: reqwest::Client,
+    base_url: String,
+    auth_token: SecretString,
+    client: reqwest::Client,
+    retry: RetryConfig,
+    cache: Option<Arc<dyn Cache>>,
+    // Overrides any TTL derived from the response (e.g. an interval's
+    // end_time) when the caller wants a fixed `--cache-ttl`.
+    cache_ttl_override: Option<Duration>,
 }
 
 #[derive(Error, Debug)]
@@ -93,71 +289,472 @@ pub enum Error {
 }
 
 impl RestClient {
-    pub fn new_client(url: String, auth_token: String) -> Self {
+    pub fn new_client(base_url: String, auth_token: SecretString) -> Self {
+        Self::new_client_with_retry(base_url, auth_token, RetryConfig::default())
+    }
+
+    pub fn new_client_with_retry(
+        base_url: String,
+        auth_token: SecretString,
+        retry: RetryConfig,
+    ) -> Self {
+        // gzip + HTTP/2 are pulled in via this crate's reqwest feature flags;
+        // a single client here means every route shares one TLS/connection pool.
+        let client = Client::builder()
+            .gzip(true)
+            .build()
+            .expect("failed to build reqwest client");
+
         Self {
-            url,
+            base_url,
             auth_token,
-            client: Client::new(),
+            client,
+            retry,
+            cache: None,
+            cache_ttl_override: None,
         }
     }
 
-    pub async fn get_site_data(&mut self) -> Result<Vec<SiteDetails>, Error> {
-        let auth_token_header = format!("Bearer {}", &self.auth_token);
-
-        let response = self
-            .client
-            .get(&self.url)
-            .header("AUTHORIZATION", auth_token_header)
-            .header("CONTENT_TYPE", "application/json")
-            .header("ACCEPT", "application/json")
-            .send()
-            .await?;
-        match response.status() {
-            reqwest::StatusCode::OK => {
-                let response = response.json::<Vec<SiteDetails>>().await?;
+    pub fn with_cache(mut self, cache: Arc<dyn Cache>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    pub fn with_cache_ttl_override(mut self, ttl: Duration) -> Self {
+        self.cache_ttl_override = Some(ttl);
+        self
+    }
+
+    // Sends a GET to `{base_url}{path}?{query}`, retrying transport errors
+    // and 429/5xx responses with exponential backoff + jitter, honoring
+    // `Retry-After` when the server sends one.
+    async fn request(&self, path: &str, query: &[(&str, String)]) -> Result<Response, Error> {
+        let auth_token_header = format!("Bearer {}", &self.auth_token.expose_secret());
+        let url = format!("{}{}", self.base_url, path);
+        // Always make at least one attempt, even if max_attempts is 0.
+        let max_attempts = self.retry.max_attempts.max(1);
+
+        for attempt in 1..=max_attempts {
+            let result = self
+                .client
+                .get(&url)
+                .query(query)
+                .header("AUTHORIZATION", &auth_token_header)
+                .header("CONTENT_TYPE", "application/json")
+                .header("ACCEPT", "application/json")
+                .send()
+                .await;
+
+            let response = match result {
+                Ok(response) => response,
+                Err(err) => {
+                    if attempt == max_attempts {
+                        return Err(Error::ReqwestError(err));
+                    }
+                    sleep(self.backoff_delay(attempt)).await;
+                    continue;
+                }
+            };
+
+            if response.status().is_success() {
                 return Ok(response);
             }
-            //_ => return Err(Error::FuckedOut(response.status().to_string())),
-            _ => {
-                return Err(Error::HttpNon200Status {
-                    status_code: (response.status().to_string()),
-                    body: (response.text().await)?,
-                })
+
+            if attempt < max_attempts && is_retryable(response.status()) {
+                let delay = retry_after_delay(&response).unwrap_or_else(|| self.backoff_delay(attempt));
+                sleep(delay).await;
+                continue;
+            }
+
+            return Err(Error::HttpNon200Status {
+                status_code: response.status().to_string(),
+                body: response.text().await?,
+            });
+        }
+
+        unreachable!("retry loop always returns before exhausting max_attempts")
+    }
+
+    fn backoff_delay(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(16);
+        let delay = self
+            .retry
+            .base_delay
+            .saturating_mul(1u32.checked_shl(exponent).unwrap_or(u32::MAX))
+            .min(self.retry.max_delay);
+
+        jitter(delay)
+    }
+
+    // Fetches `path`, serving from (and populating) the cache when one is
+    // configured. `ttl_from` derives a TTL from the decoded body (e.g. an
+    // interval's end_time); `default_ttl` is used when it returns None.
+    // `--cache-ttl` always overrides both.
+    async fn cached_request<T>(
+        &self,
+        path: &str,
+        query: &[(&str, String)],
+        default_ttl: Duration,
+        ttl_from: impl Fn(&T) -> Option<Duration>,
+    ) -> Result<T, Error>
+    where
+        T: DeserializeOwned,
+    {
+        let key = self.cache_key(path, query);
+
+        if let Some(cache) = &self.cache {
+            if let Some(bytes) = cache.get(&key).await {
+                return Ok(serde_json::from_slice(&bytes)?);
             }
         }
+
+        let response = self.request(path, query).await?;
+        let bytes = response.bytes().await?.to_vec();
+        let decoded: T = serde_json::from_slice(&bytes)?;
+
+        if let Some(cache) = &self.cache {
+            let ttl = self
+                .cache_ttl_override
+                .unwrap_or_else(|| ttl_from(&decoded).unwrap_or(default_ttl));
+            cache.set(&key, bytes, ttl).await;
+        }
+
+        Ok(decoded)
+    }
+
+    pub async fn sites(&self) -> Result<Vec<SiteDetails>, Error> {
+        self.cached_request("/sites", &[], SITE_CACHE_TTL, |_| None)
+            .await
+    }
+
+    pub async fn prices_current(
+        &self,
+        site_id: &str,
+        resolution: u16,
+    ) -> Result<Vec<PriceInterval>, Error> {
+        let path = format!("/sites/{}/prices/current", site_id);
+        let query = [("resolution", resolution.to_string())];
+
+        self.cached_request(
+            &path,
+            &query,
+            DEFAULT_INTERVAL_CACHE_TTL,
+            |intervals: &Vec<PriceInterval>| ttl_until_next_interval(intervals),
+        )
+        .await
+    }
+
+    pub async fn price_forecast(
+        &self,
+        site_id: &str,
+        resolution: u16,
+        next: Option<u32>,
+        previous: Option<u32>,
+    ) -> Result<Vec<PriceInterval>, Error> {
+        let path = format!("/sites/{}/prices", site_id);
+        let mut query = vec![("resolution", resolution.to_string())];
+        if let Some(next) = next {
+            query.push(("next", next.to_string()));
+        }
+        if let Some(previous) = previous {
+            query.push(("previous", previous.to_string()));
+        }
+
+        self.cached_request(
+            &path,
+            &query,
+            DEFAULT_INTERVAL_CACHE_TTL,
+            |intervals: &Vec<PriceInterval>| ttl_until_next_interval(intervals),
+        )
+        .await
     }
 
-    pub async fn get_current_price_data(&mut self) -> Result<Vec<CurrentPrices>> {
-        let auth_token_header = format!("Bearer {}", &self.auth_token);
+    pub async fn usage(
+        &self,
+        site_id: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+        resolution: u16,
+    ) -> Result<Vec<CurrentUsage>, Error> {
+        let path = format!("/sites/{}/usage", site_id);
+        let query = [
+            ("startDate", start.format("%Y-%m-%d").to_string()),
+            ("endDate", end.format("%Y-%m-%d").to_string()),
+            ("resolution", resolution.to_string()),
+        ];
 
-        let response = self
-            .client
-            .get(&self.url)
-            .header("AUTHORIZATION", auth_token_header)
-            .header("CONTENT_TYPE", "application/json")
-            .header("ACCEPT", "application/json")
-            .send()
-            .await?
-            .json::<Vec<CurrentPrices>>()
-            .await?;
+        self.cached_request(
+            &path,
+            &query,
+            DEFAULT_INTERVAL_CACHE_TTL,
+            |usage: &Vec<CurrentUsage>| {
+                usage.iter().filter_map(|u| ttl_until(&u.end_time)).max()
+            },
+        )
+        .await
+    }
+
+    // Prefixes the key with a fingerprint of base_url+auth_token so two
+    // accounts (or a prod/staging base_url) sharing a cache backend never
+    // read each other's cached responses; the token itself is only hashed,
+    // never written out.
+    fn cache_key(&self, path: &str, query: &[(&str, String)]) -> String {
+        let mut hasher = DefaultHasher::new();
+        self.base_url.hash(&mut hasher);
+        self.auth_token.expose_secret().hash(&mut hasher);
+        let account = hasher.finish();
+
+        let mut key = format!("{:016x}|{}", account, path);
+        for (name, value) in query {
+            key.push('|');
+            key.push_str(name);
+            key.push('=');
+            key.push_str(value);
+        }
+        key
+    }
+}
+
+// Parses `end_time`'s ISO8601 rendering rather than reaching into
+// `iso8601_timestamp`'s internals, since all we need is its Display output.
+fn ttl_until(end_time: &Timestamp) -> Option<Duration> {
+    let end = DateTime::parse_from_rfc3339(&end_time.to_string()).ok()?;
+    (end.with_timezone(&Utc) - Utc::now()).to_std().ok()
+}
 
-        Ok(response)
+fn end_time_of(interval: &PriceInterval) -> &Timestamp {
+    match interval {
+        PriceInterval::ActualInterval { end_time, .. }
+        | PriceInterval::CurrentInterval { end_time, .. }
+        | PriceInterval::ForecastInterval { end_time, .. } => end_time,
     }
+}
+
+fn ttl_until_next_interval(intervals: &[PriceInterval]) -> Option<Duration> {
+    // The cache entry must expire when the *next* interval starts, not when
+    // the furthest-future one ends, or a forecast fetched with `--next N`
+    // stays "current" long after the first interval has passed.
+    intervals
+        .iter()
+        .filter_map(|interval| ttl_until(end_time_of(interval)))
+        .min()
+}
+
+fn is_retryable(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+// +/-50% jitter so concurrent callers don't retry in lockstep.
+fn jitter(delay: Duration) -> Duration {
+    let factor = rand::thread_rng().gen_range(0.5..=1.5);
+    Duration::from_secs_f64(delay.as_secs_f64() * factor)
+}
 
-    pub async fn get_usage_data(&mut self) -> Result<Vec<CurrentUsage>> {
-        let auth_token_header = format!("Bearer {}", &self.auth_token);
+fn retry_after_delay(response: &Response) -> Option<Duration> {
+    let value = response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?;
 
-        let response = self
-            .client
-            .get(&self.url)
-            .header("AUTHORIZATION", auth_token_header)
-            .header("CONTENT_TYPE", "application/json")
-            .header("ACCEPT", "application/json")
-            .send()
-            .await?
-            .json::<Vec<CurrentUsage>>()
-            .await?;
+    parse_retry_after(value)
+}
+
+// Split out of `retry_after_delay` so the seconds-vs-HTTP-date parsing can be
+// exercised directly without a live response.
+fn parse_retry_after(value: &str) -> Option<Duration> {
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let retry_at = httpdate::parse_http_date(value).ok()?;
+    retry_at.duration_since(std::time::SystemTime::now()).ok()
+}
 
-        Ok(response)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_client() -> RestClient {
+        RestClient::new_client(
+            "https://example.invalid".to_string(),
+            SecretString::new("token".to_string()),
+        )
+    }
+
+    #[test]
+    fn backoff_delay_doubles_per_attempt_within_jitter_bounds() {
+        let client = test_client();
+
+        // base_delay=500ms, +/-50% jitter => attempt 1 in [250ms, 750ms].
+        let first = client.backoff_delay(1);
+        assert!(first >= Duration::from_millis(250) && first <= Duration::from_millis(750));
+
+        // attempt 4 -> 500ms * 2^3 = 4s, jitter [2s, 6s].
+        let fourth = client.backoff_delay(4);
+        assert!(fourth >= Duration::from_secs(2) && fourth <= Duration::from_secs(6));
+    }
+
+    #[test]
+    fn backoff_delay_caps_at_max_delay() {
+        let client = test_client();
+
+        // any large attempt count must cap at max_delay=30s, jitter [15s, 45s].
+        let capped = client.backoff_delay(20);
+        assert!(capped >= Duration::from_secs(15) && capped <= Duration::from_secs(45));
+    }
+
+    #[test]
+    fn cache_key_differs_between_auth_tokens_on_the_same_base_url() {
+        let a = RestClient::new_client(
+            "https://api.example".to_string(),
+            SecretString::new("token-a".to_string()),
+        );
+        let b = RestClient::new_client(
+            "https://api.example".to_string(),
+            SecretString::new("token-b".to_string()),
+        );
+        assert_ne!(a.cache_key("/sites", &[]), b.cache_key("/sites", &[]));
+    }
+
+    #[test]
+    fn cache_key_differs_between_base_urls_for_the_same_token() {
+        let a = RestClient::new_client(
+            "https://api.example".to_string(),
+            SecretString::new("token".to_string()),
+        );
+        let b = RestClient::new_client(
+            "https://staging.example".to_string(),
+            SecretString::new("token".to_string()),
+        );
+        assert_ne!(a.cache_key("/sites", &[]), b.cache_key("/sites", &[]));
+    }
+
+    #[test]
+    fn is_retryable_covers_429_and_5xx_only() {
+        assert!(is_retryable(StatusCode::TOO_MANY_REQUESTS));
+        assert!(is_retryable(StatusCode::INTERNAL_SERVER_ERROR));
+        assert!(is_retryable(StatusCode::SERVICE_UNAVAILABLE));
+        assert!(!is_retryable(StatusCode::BAD_REQUEST));
+        assert!(!is_retryable(StatusCode::OK));
+    }
+
+    #[test]
+    fn parse_retry_after_accepts_seconds() {
+        assert_eq!(parse_retry_after("120"), Some(Duration::from_secs(120)));
+    }
+
+    #[test]
+    fn parse_retry_after_accepts_an_http_date_in_the_future() {
+        let future = httpdate::fmt_http_date(std::time::SystemTime::now() + Duration::from_secs(60));
+        let delay = parse_retry_after(&future).expect("should parse an HTTP-date Retry-After");
+        assert!(delay.as_secs() <= 61);
+    }
+
+    #[test]
+    fn parse_retry_after_rejects_garbage() {
+        assert_eq!(parse_retry_after("not-a-valid-value"), None);
+    }
+
+    #[test]
+    fn spike_status_decodes_known_variants_and_round_trips() {
+        for (json, expected) in [
+            (r#""none""#, SpikeStatus::None),
+            (r#""potential""#, SpikeStatus::Potential),
+            (r#""spike""#, SpikeStatus::Spike),
+        ] {
+            let decoded: SpikeStatus = serde_json::from_str(json).unwrap();
+            assert_eq!(decoded, expected);
+            assert_eq!(serde_json::to_string(&decoded).unwrap(), json);
+        }
+    }
+
+    #[test]
+    fn spike_status_falls_back_to_unknown_for_unrecognised_values() {
+        let decoded: SpikeStatus = serde_json::from_str(r#""extreme""#).unwrap();
+        assert_eq!(decoded, SpikeStatus::Unknown("extreme".to_string()));
+    }
+
+    #[test]
+    fn price_descriptor_falls_back_to_unknown_for_unrecognised_values() {
+        let decoded: PriceDescriptor = serde_json::from_str(r#""mega_spike""#).unwrap();
+        assert_eq!(decoded, PriceDescriptor::Unknown("mega_spike".to_string()));
+    }
+
+    const ACTUAL_INTERVAL_JSON: &str = r#"{
+        "type": "ActualInterval",
+        "date": "2024-01-01T00:00:00Z",
+        "duration": 30,
+        "startTime": "2024-01-01T00:00:00Z",
+        "endTime": "2024-01-01T00:30:00Z",
+        "perKwh": 20.0,
+        "renewables": 50.0,
+        "spotPerKwh": 10.0,
+        "channelType": "general",
+        "spikeStatus": "none",
+        "tariffInformation": {"period": "offPeak"},
+        "descriptor": "low"
+    }"#;
+
+    const CURRENT_INTERVAL_JSON: &str = r#"{
+        "type": "CurrentInterval",
+        "date": "2024-01-01T00:00:00Z",
+        "duration": 30,
+        "startTime": "2024-01-01T00:00:00Z",
+        "endTime": "2024-01-01T00:30:00Z",
+        "nemTime": "2024-01-01T00:30:00Z",
+        "perKwh": 20.0,
+        "renewables": 50.0,
+        "spotPerKwh": 10.0,
+        "channelType": "general",
+        "spikeStatus": "none",
+        "tariffInformation": {"period": "offPeak"},
+        "descriptor": "low",
+        "estimate": false
+    }"#;
+
+    const FORECAST_INTERVAL_JSON: &str = r#"{
+        "type": "ForecastInterval",
+        "date": "2024-01-01T00:00:00Z",
+        "duration": 30,
+        "startTime": "2024-01-01T01:00:00Z",
+        "endTime": "2024-01-01T01:30:00Z",
+        "perKwh": 22.0,
+        "renewables": 40.0,
+        "spotPerKwh": 11.0,
+        "channelType": "general",
+        "spikeStatus": "potential",
+        "tariffInformation": {"period": "peak"},
+        "descriptor": "high",
+        "estimate": true,
+        "range": {"min": 18.0, "max": 26.0}
+    }"#;
+
+    #[test]
+    fn price_interval_decodes_actual_variant() {
+        let interval: PriceInterval = serde_json::from_str(ACTUAL_INTERVAL_JSON).unwrap();
+        assert!(matches!(interval, PriceInterval::ActualInterval { .. }));
+    }
+
+    #[test]
+    fn price_interval_decodes_current_variant() {
+        let interval: PriceInterval = serde_json::from_str(CURRENT_INTERVAL_JSON).unwrap();
+        match interval {
+            PriceInterval::CurrentInterval { estimate, .. } => assert!(!estimate),
+            other => panic!("expected CurrentInterval, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn price_interval_decodes_forecast_variant_with_range() {
+        let interval: PriceInterval = serde_json::from_str(FORECAST_INTERVAL_JSON).unwrap();
+        match interval {
+            PriceInterval::ForecastInterval { range, .. } => {
+                let range = range.expect("forecast interval should carry a range");
+                assert_eq!(range.min, 18.0);
+                assert_eq!(range.max, 26.0);
+            }
+            other => panic!("expected ForecastInterval, got {:?}", other),
+        }
     }
 }